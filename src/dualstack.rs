@@ -0,0 +1,153 @@
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+
+use reqwest::{Client, ClientBuilder, Url};
+use tokio::io::{BufReader, Lines, Stdin};
+use tokio::sync::Semaphore;
+use tokio::task::JoinHandle;
+
+use crate::output::print_result;
+use crate::{get_url_variants, parse_regexes, process_url, tls, Config};
+
+/// Which address families to probe, derived from `-4`/`-6` (both, when
+/// neither is given).
+fn wanted_families(config: &Config) -> (bool, bool) {
+    match (config.ipv4, config.ipv6) {
+        (false, false) => (true, true),
+        (v4, v6) => (v4, v6),
+    }
+}
+
+/// Resolves every A/AAAA address for `host`, filtered to the wanted families.
+async fn resolve_ips(host: &str, want_v4: bool, want_v6: bool) -> Vec<IpAddr> {
+    let mut ips: Vec<IpAddr> = match tokio::net::lookup_host((host, 0)).await {
+        Ok(addrs) => addrs.map(|addr| addr.ip()).collect(),
+        Err(_) => return vec![],
+    };
+
+    ips.retain(|ip| match ip {
+        IpAddr::V4(_) => want_v4,
+        IpAddr::V6(_) => want_v6,
+    });
+    ips.sort();
+    ips.dedup();
+    ips
+}
+
+fn base_client_builder(timeout_ms: u64, cert_capture: tls::CertCapture) -> ClientBuilder {
+    Client::builder()
+        .use_preconfigured_tls(tls::recording_client_config(cert_capture))
+        .timeout(std::time::Duration::from_millis(timeout_ms))
+        .redirect(reqwest::redirect::Policy::none())
+        .tcp_keepalive(None)
+        .tcp_nodelay(true)
+        .https_only(false)
+        .pool_max_idle_per_host(0)
+        .user_agent("httprs/0.1.0")
+}
+
+/// Resolves each host to every wanted A/AAAA address and probes them
+/// separately, pinning the connection to a single address at a time with
+/// reqwest's `.resolve()` override while leaving the `Host:` header intact.
+pub async fn resolve_all(
+    mut host_lines: Lines<BufReader<Stdin>>,
+    config: &Config,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let regexes = if let Some(path) = &config.match_regexes_path {
+        parse_regexes(path).await
+    } else {
+        vec![]
+    };
+
+    let (want_v4, want_v6) = wanted_families(config);
+    let semaphore = Arc::new(Semaphore::new(config.tasks));
+    let mut handles: Vec<JoinHandle<()>> = vec![];
+
+    while let Some(host) = host_lines.next_line().await.unwrap() {
+        let permit = semaphore
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("failed to acquire permit");
+
+        let handle_finished_indices: Vec<usize> = handles
+            .iter()
+            .enumerate()
+            .filter(|(_, handle)| handle.is_finished())
+            .map(|(index, _)| index)
+            .rev()
+            .collect();
+
+        for index in handle_finished_indices {
+            handles.swap_remove(index);
+        }
+
+        let regexes = regexes.clone();
+        let range_bytes = config.range_bytes;
+        let tls_info = config.tls_info;
+        let timeout = config.timeout;
+        let output = config.output;
+        let cert_capture = tls::CertCapture::new();
+
+        handles.push(tokio::spawn(async move {
+            let variants = get_url_variants(host);
+
+            // The hostname (and therefore its A/AAAA records) is the same
+            // across scheme variants, so resolve it once up front instead of
+            // once per variant.
+            let hostname = variants
+                .first()
+                .and_then(|url| Url::parse(url).ok())
+                .and_then(|parsed| parsed.host_str().map(str::to_string));
+
+            if let Some(hostname) = hostname {
+                let ips = resolve_ips(&hostname, want_v4, want_v6).await;
+
+                // Per address: prefer https, fall back to http, and stop at
+                // the first variant that answers — one line per address,
+                // matching `process`'s scheme preference.
+                for ip in ips {
+                    for url in &variants {
+                        let Ok(parsed) = Url::parse(url) else {
+                            continue;
+                        };
+                        let Some(port) = parsed.port_or_known_default() else {
+                            continue;
+                        };
+
+                        let cert_capture = cert_capture.clone();
+                        let client = match base_client_builder(timeout, cert_capture.clone())
+                            .resolve(&hostname, SocketAddr::new(ip, port))
+                            .build()
+                        {
+                            Ok(client) => client,
+                            Err(_) => continue,
+                        };
+
+                        if let Some(mut result) =
+                            process_url(&client, url, &regexes, range_bytes).await
+                        {
+                            result.resolved_ip = Some(ip.to_string());
+                            if tls_info && url.starts_with("https://") {
+                                result.tls = cert_capture
+                                    .get(&hostname)
+                                    .and_then(|der| tls::describe(&der));
+                            }
+
+                            print_result(&result, output);
+                            break;
+                        }
+                    }
+                }
+            }
+
+            drop(permit);
+        }));
+    }
+
+    for handle in handles {
+        handle.await.expect("fatal error in a task")
+    }
+
+    Ok(())
+}
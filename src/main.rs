@@ -2,16 +2,24 @@ use std::path::PathBuf;
 use std::sync::Arc;
 
 use clap::Parser;
-use colored::{Color, Colorize};
+use futures_util::StreamExt;
 use regex::bytes::Regex;
-use reqwest::Client;
+use reqwest::header::RANGE;
+use reqwest::{Client, StatusCode};
 use tokio::io::{AsyncBufReadExt, BufReader, Lines, Stdin};
 use tokio::sync::Semaphore;
 use tokio::task::JoinHandle;
 
+mod dualstack;
+mod output;
+mod tls;
+mod watch;
+
+use output::{print_result, selected_headers, OutputFormat, ProbeResult};
+
 #[derive(Parser)]
 #[command(version, about = "🧨 http toolkit that allows probing many hosts.")]
-struct Config {
+pub(crate) struct Config {
     /// Timeout in milliseconds
     #[arg(
         short = 'T',
@@ -20,7 +28,7 @@ struct Config {
         help_heading = "Optimizations ⚙️",
         help = "request duration threshold in milliseconds"
     )]
-    timeout: u64,
+    pub(crate) timeout: u64,
 
     /// Number of concurrent requests
     #[arg(
@@ -30,7 +38,7 @@ struct Config {
         help_heading = "Rate-Limit 🐌",
         help = "number of concurrent requests"
     )]
-    tasks: usize,
+    pub(crate) tasks: usize,
 
     /// Regular expression to match
     #[arg(
@@ -39,10 +47,79 @@ struct Config {
         help_heading = "Matchers 🔍",
         help = "path to a list of regex patterns"
     )]
-    match_regexes_path: Option<PathBuf>,
+    pub(crate) match_regexes_path: Option<PathBuf>,
+
+    /// Only fetch the first N bytes of each response via a Range request
+    #[arg(
+        short = 'R',
+        long = "range-bytes",
+        help_heading = "Optimizations ⚙️",
+        help = "probe with `Range: bytes=0-(N-1)` instead of downloading full bodies"
+    )]
+    pub(crate) range_bytes: Option<u64>,
+
+    /// Keep polling each host for appended bytes instead of exiting after one pass
+    #[arg(
+        long = "watch",
+        help_heading = "Watch 👁️",
+        help = "tail appended content over HTTP via incremental Range offsets"
+    )]
+    pub(crate) watch: bool,
+
+    /// Poll interval in milliseconds, used with --watch
+    #[arg(
+        long = "interval",
+        default_value_t = 5000,
+        help_heading = "Watch 👁️",
+        help = "milliseconds between polls in --watch mode"
+    )]
+    pub(crate) interval: u64,
+
+    /// Print TLS certificate subject/issuer/validity and fingerprints
+    #[arg(
+        long = "tls-info",
+        help_heading = "TLS 🔒",
+        help = "capture and print each host's leaf certificate fingerprint"
+    )]
+    pub(crate) tls_info: bool,
+
+    /// Resolve every A/AAAA address for a host and probe each one separately
+    #[arg(
+        long = "resolve-all",
+        help_heading = "Resolution 🌐",
+        help = "dual-stack fan-out: probe every resolved address, not just one"
+    )]
+    pub(crate) resolve_all: bool,
+
+    /// Only probe IPv4 addresses in --resolve-all mode
+    #[arg(
+        short = '4',
+        help_heading = "Resolution 🌐",
+        help = "restrict --resolve-all to IPv4 addresses"
+    )]
+    pub(crate) ipv4: bool,
+
+    /// Only probe IPv6 addresses in --resolve-all mode
+    #[arg(
+        short = '6',
+        help_heading = "Resolution 🌐",
+        help = "restrict --resolve-all to IPv6 addresses"
+    )]
+    pub(crate) ipv6: bool,
+
+    /// Output format: plain text or one JSON object per line
+    #[arg(
+        short = 'o',
+        long = "output",
+        value_enum,
+        default_value = "plain",
+        help_heading = "Output 📤",
+        help = "emit plain text or structured JSON lines"
+    )]
+    pub(crate) output: OutputFormat,
 }
 
-async fn parse_regexes(path: &PathBuf) -> Vec<Regex> {
+pub(crate) async fn parse_regexes(path: &PathBuf) -> Vec<Regex> {
     match tokio::fs::read_to_string(path).await {
         Ok(text) => text
             .split('\n')
@@ -53,7 +130,7 @@ async fn parse_regexes(path: &PathBuf) -> Vec<Regex> {
     }
 }
 
-fn get_url_variants(host: String) -> Vec<String> {
+pub(crate) fn get_url_variants(host: String) -> Vec<String> {
     return if host.starts_with("https://") || host.starts_with("http://") {
         vec![host]
     } else {
@@ -64,48 +141,124 @@ fn get_url_variants(host: String) -> Vec<String> {
     };
 }
 
-async fn process_url(client: &Client, url: &String, regexes: &Vec<Regex>) -> Option<Vec<String>> {
-    match client.get(url).send().await {
-        Err(_) => None,
-        Ok(res) => match regexes.is_empty() {
-            true => Some(vec![]),
-            false => match res.bytes().await {
-                Err(_) => None,
-                Ok(bytes) => {
-                    let bytes = bytes.as_ref();
-                    let matches = regexes
-                        .iter()
-                        .filter_map(|re| re.captures(bytes))
-                        .filter_map(|caps| caps.get(1).or(caps.get(0)))
-                        .map(|m| m.as_bytes())
-                        .map(|value| String::from_utf8_lossy(value).to_string())
-                        .collect::<Vec<String>>();
-                    return if !matches.is_empty() {
-                        Some(matches)
-                    } else {
-                        None
-                    };
-                }
-            },
-        },
+/// Reads at most `cap` bytes from a response body, stopping early instead of
+/// draining the rest of the stream. With no cap the whole body is buffered.
+async fn read_body_capped(res: reqwest::Response, cap: Option<u64>) -> reqwest::Result<Vec<u8>> {
+    let Some(cap) = cap else {
+        return Ok(res.bytes().await?.to_vec());
+    };
+
+    let cap = cap as usize;
+    // Pre-allocate only up to a sane chunk size; a huge `-R` value shouldn't
+    // translate into a huge upfront allocation, since we grow as needed anyway.
+    let mut buf = Vec::with_capacity(cap.min(64 * 1024));
+    let mut stream = res.bytes_stream();
+
+    while buf.len() < cap {
+        match stream.next().await {
+            Some(chunk) => buf.extend_from_slice(&chunk?),
+            None => break,
+        }
     }
+
+    buf.truncate(cap);
+    Ok(buf)
 }
 
-async fn process(
-    mut host_lines: Lines<BufReader<Stdin>>,
-    config: &Config,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let client = Client::builder()
-        .danger_accept_invalid_certs(true)
-        .timeout(std::time::Duration::from_millis(config.timeout))
+pub(crate) async fn process_url(
+    client: &Client,
+    url: &String,
+    regexes: &Vec<Regex>,
+    range_bytes: Option<u64>,
+) -> Option<ProbeResult> {
+    let mut req = client.get(url);
+    if let Some(n) = range_bytes {
+        req = req.header(RANGE, format!("bytes=0-{}", n.saturating_sub(1)));
+    }
+
+    let start = std::time::Instant::now();
+    let mut res = req.send().await.ok()?;
+
+    // A server that can't satisfy the range isn't a reason to drop the host
+    // entirely: ask once more without the Range header; the body read below
+    // is still capped to `range_bytes`.
+    if range_bytes.is_some() && res.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+        res = client.get(url).send().await.ok()?;
+    }
+
+    let status = res.status();
+    let headers = selected_headers(res.headers());
+    let content_length = res.content_length();
+
+    // Don't pay for a body read at all when there's nothing to match against.
+    let bytes = if regexes.is_empty() {
+        vec![]
+    } else {
+        read_body_capped(res, range_bytes).await.ok()?
+    };
+    let elapsed_ms = start.elapsed().as_millis();
+
+    let matches = regexes
+        .iter()
+        .filter_map(|re| re.captures(bytes.as_slice()))
+        .filter_map(|caps| caps.get(1).or(caps.get(0)))
+        .map(|m| m.as_bytes())
+        .map(|value| String::from_utf8_lossy(value).to_string())
+        .collect::<Vec<String>>();
+
+    if !regexes.is_empty() && matches.is_empty() {
+        return None;
+    }
+
+    Some(ProbeResult {
+        url: url.clone(),
+        status: status.as_u16(),
+        elapsed_ms,
+        content_length,
+        headers,
+        matches,
+        resolved_ip: None,
+        tls: None,
+    })
+}
+
+/// Applies the settings shared by every client this tool builds.
+fn configure_client(builder: reqwest::ClientBuilder, timeout_ms: u64) -> reqwest::ClientBuilder {
+    builder
+        .timeout(std::time::Duration::from_millis(timeout_ms))
         .redirect(reqwest::redirect::Policy::none())
         .tcp_keepalive(None)
         .tcp_nodelay(true)
         .https_only(false)
         .pool_max_idle_per_host(0)
         .user_agent("httprs/0.1.0")
+}
+
+async fn process(
+    mut host_lines: Lines<BufReader<Stdin>>,
+    config: &Config,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    // Only pay for the rustls backend and its cert-recording verifier (an
+    // unbounded per-host map for the run's lifetime) when --tls-info is
+    // actually going to read it back out.
+    let cert_capture = tls::CertCapture::new();
+    let client = if config.tls_info {
+        configure_client(
+            Client::builder().use_preconfigured_tls(tls::recording_client_config(
+                cert_capture.clone(),
+            )),
+            config.timeout,
+        )
+        .build()
+        .unwrap()
+    } else {
+        configure_client(
+            Client::builder().danger_accept_invalid_certs(true),
+            config.timeout,
+        )
         .build()
-        .unwrap();
+        .unwrap()
+    };
 
     let regexes = if let Some(path) = &config.match_regexes_path {
         parse_regexes(&path).await
@@ -137,15 +290,24 @@ async fn process(
 
         let regexes = regexes.clone();
         let client = client.clone();
+        let range_bytes = config.range_bytes;
+        let cert_capture = cert_capture.clone();
+        let tls_info = config.tls_info;
+        let output = config.output;
 
         handles.push(tokio::spawn(async move {
             for url in get_url_variants(host) {
-                if let Some(matches) = process_url(&client, &url, &regexes).await {
-                    if !matches.is_empty() {
-                        println!("{} {}", url, matches.join(" ").color(Color::Cyan));
-                    } else {
-                        println!("{}", url);
+                if let Some(mut result) = process_url(&client, &url, &regexes, range_bytes).await
+                {
+                    if tls_info && url.starts_with("https://") {
+                        result.tls = reqwest::Url::parse(&url)
+                            .ok()
+                            .and_then(|parsed| parsed.host_str().map(str::to_string))
+                            .and_then(|host| cert_capture.get(&host))
+                            .and_then(|der| tls::describe(&der));
                     }
+
+                    print_result(&result, output);
                     break;
                 }
             }
@@ -167,7 +329,18 @@ async fn main() {
 
     let stdin = tokio::io::stdin();
     let reader = BufReader::new(stdin);
-    process(reader.lines(), &config)
-        .await
-        .expect("error while processing input");
+
+    if config.watch {
+        watch::watch(reader.lines(), &config)
+            .await
+            .expect("error while processing input");
+    } else if config.resolve_all {
+        dualstack::resolve_all(reader.lines(), &config)
+            .await
+            .expect("error while processing input");
+    } else {
+        process(reader.lines(), &config)
+            .await
+            .expect("error while processing input");
+    }
 }
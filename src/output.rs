@@ -0,0 +1,81 @@
+use std::collections::BTreeMap;
+
+use clap::ValueEnum;
+use colored::Colorize;
+use reqwest::header::HeaderMap;
+use serde::Serialize;
+
+use crate::tls::TlsInfo;
+
+/// Headers worth surfacing in a probe result; anything else is discarded.
+const SELECTED_HEADERS: &[&str] = &["server", "content-type"];
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum OutputFormat {
+    Plain,
+    Json,
+}
+
+/// Everything `process_url` learned about one request: status, timing and a
+/// handful of headers alongside the regex matches, so both the plain and
+/// JSON formatters can be driven off the same data.
+#[derive(Serialize)]
+pub(crate) struct ProbeResult {
+    pub(crate) url: String,
+    pub(crate) status: u16,
+    pub(crate) elapsed_ms: u128,
+    pub(crate) content_length: Option<u64>,
+    pub(crate) headers: BTreeMap<String, String>,
+    pub(crate) matches: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) resolved_ip: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) tls: Option<TlsInfo>,
+}
+
+pub(crate) fn selected_headers(headers: &HeaderMap) -> BTreeMap<String, String> {
+    SELECTED_HEADERS
+        .iter()
+        .filter_map(|&name| {
+            let value = headers.get(name)?.to_str().ok()?;
+            Some((name.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// Prints a probe result either as the tool's historical plain-text line or
+/// as a single line of JSON, per `format`.
+pub(crate) fn print_result(result: &ProbeResult, format: OutputFormat) {
+    match format {
+        OutputFormat::Json => {
+            println!(
+                "{}",
+                serde_json::to_string(result).expect("failed to serialize result")
+            );
+        }
+        OutputFormat::Plain => {
+            let ip_suffix = result
+                .resolved_ip
+                .as_ref()
+                .map(|ip| format!(" [{}]", ip))
+                .unwrap_or_default();
+            let tls_suffix = result
+                .tls
+                .as_ref()
+                .map(|info| format!(" {}", info.summary()))
+                .unwrap_or_default();
+
+            if !result.matches.is_empty() {
+                println!(
+                    "{}{}{} {}",
+                    result.url,
+                    ip_suffix,
+                    tls_suffix,
+                    result.matches.join(" ").color(colored::Color::Cyan)
+                );
+            } else {
+                println!("{}{}{}", result.url, ip_suffix, tls_suffix);
+            }
+        }
+    }
+}
@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use rustls::client::{ServerCertVerified, ServerCertVerifier};
+use rustls::{Certificate, ClientConfig, Error, ServerName};
+use sha2::{Digest, Sha256};
+use x509_parser::certificate::X509Certificate;
+use x509_parser::extensions::GeneralName;
+use x509_parser::prelude::FromDer;
+
+const VOWELS: &[u8] = b"aeiouy";
+const CONSONANTS: &[u8] = b"bcdfghklmnprstvzx";
+
+/// Encodes `data` as a Bubble Babble string, the same human-comparable
+/// encoding ssh-keygen uses for key fingerprints.
+pub fn bubble_babble(data: &[u8]) -> String {
+    let mut out = String::with_capacity(data.len() * 3 + 6);
+    out.push('x');
+
+    let mut seed: u32 = 1;
+    let mut i = 0;
+    while i <= data.len() {
+        if i + 1 < data.len() {
+            let b1 = data[i] as u32;
+            let b2 = data[i + 1] as u32;
+            out.push(VOWELS[(((b1 >> 6) & 3) + seed) as usize % 6] as char);
+            out.push(CONSONANTS[((b1 >> 2) & 15) as usize] as char);
+            out.push(VOWELS[((b1 & 3) + seed / 6) as usize % 6] as char);
+            out.push(CONSONANTS[((b2 >> 4) & 15) as usize] as char);
+            out.push('-');
+            out.push(CONSONANTS[(b2 & 15) as usize] as char);
+            seed = (seed * 5 + b1 * 7 + b2) % 36;
+        } else if i < data.len() {
+            let b1 = data[i] as u32;
+            out.push(VOWELS[(((b1 >> 6) & 3) + seed) as usize % 6] as char);
+            out.push(CONSONANTS[((b1 >> 2) & 15) as usize] as char);
+            out.push(VOWELS[((b1 & 3) + seed / 6) as usize % 6] as char);
+        } else {
+            out.push(VOWELS[(seed % 6) as usize] as char);
+            out.push(CONSONANTS[16] as char);
+            out.push(VOWELS[(seed / 6) as usize] as char);
+        }
+        i += 2;
+    }
+
+    out.push('x');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bubble_babble_empty_input() {
+        assert_eq!(bubble_babble(b""), "xexax");
+    }
+
+    #[test]
+    fn bubble_babble_even_length_known_vector() {
+        assert_eq!(
+            bubble_babble(b"1234567890"),
+            "xesef-disof-gytuf-katof-movif-baxux"
+        );
+    }
+
+    #[test]
+    fn bubble_babble_odd_length_uses_data_derived_consonant() {
+        // A single odd trailing byte must contribute its own middle
+        // consonant (`(b1 >> 2) & 15`), not the fixed 'x' terminator that's
+        // reserved for the zero-byte/seed-only case.
+        assert_eq!(bubble_babble(b"1"), "xesex");
+    }
+}
+
+/// Subject, issuer and fingerprint details pulled from a leaf certificate's
+/// DER bytes, for display behind `--tls-info`.
+#[derive(serde::Serialize)]
+pub struct TlsInfo {
+    pub subject: String,
+    pub issuer: String,
+    pub not_before: String,
+    pub not_after: String,
+    pub sans: Vec<String>,
+    pub sha256_hex: String,
+    pub bubble_babble: String,
+}
+
+/// Renders an IP SAN's raw bytes as a real address: 4 bytes as IPv4, 16 as
+/// IPv6, falling back to dotted bytes for anything malformed.
+fn format_ip_san(bytes: &[u8]) -> String {
+    if let Ok(octets) = <[u8; 4]>::try_from(bytes) {
+        return Ipv4Addr::from(octets).to_string();
+    }
+    if let Ok(octets) = <[u8; 16]>::try_from(bytes) {
+        return Ipv6Addr::from(octets).to_string();
+    }
+    bytes
+        .iter()
+        .map(|b| b.to_string())
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Parses a leaf certificate's DER bytes into display-friendly fields and a
+/// fingerprint of the DER itself.
+pub fn describe(der: &[u8]) -> Option<TlsInfo> {
+    let (_, cert) = X509Certificate::from_der(der).ok()?;
+
+    let sans = cert
+        .subject_alternative_name()
+        .ok()
+        .flatten()
+        .map(|ext| {
+            ext.value
+                .general_names
+                .iter()
+                .map(|name| match name {
+                    GeneralName::DNSName(dns) => dns.to_string(),
+                    GeneralName::IPAddress(ip) => format_ip_san(ip),
+                    other => format!("{:?}", other),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let digest = Sha256::digest(der);
+
+    Some(TlsInfo {
+        subject: cert.subject().to_string(),
+        issuer: cert.issuer().to_string(),
+        not_before: cert.validity().not_before.to_string(),
+        not_after: cert.validity().not_after.to_string(),
+        sans,
+        sha256_hex: digest.iter().map(|b| format!("{:02x}", b)).collect(),
+        bubble_babble: bubble_babble(digest.as_slice()),
+    })
+}
+
+impl TlsInfo {
+    pub fn summary(&self) -> String {
+        format!(
+            "[tls subject={} issuer={} not_before={} not_after={} sans={} sha256={} bubblebabble={}]",
+            self.subject,
+            self.issuer,
+            self.not_before,
+            self.not_after,
+            self.sans.join(","),
+            self.sha256_hex,
+            self.bubble_babble,
+        )
+    }
+}
+
+/// Shared store of leaf certificate DER bytes captured per host during the
+/// TLS handshake, keyed by the server name requested.
+#[derive(Clone, Default)]
+pub struct CertCapture {
+    store: Arc<Mutex<HashMap<String, Vec<u8>>>>,
+}
+
+impl CertCapture {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, host: &str) -> Option<Vec<u8>> {
+        self.store.lock().unwrap().get(host).cloned()
+    }
+}
+
+/// A `ServerCertVerifier` that records the leaf certificate's DER bytes and
+/// then accepts the chain unconditionally, mirroring this tool's existing
+/// `danger_accept_invalid_certs(true)` posture while letting us inspect what
+/// was actually presented.
+struct RecordingVerifier {
+    capture: CertCapture,
+}
+
+impl ServerCertVerifier for RecordingVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &Certificate,
+        _intermediates: &[Certificate],
+        server_name: &ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: SystemTime,
+    ) -> Result<ServerCertVerified, Error> {
+        let key = match server_name {
+            ServerName::DnsName(name) => name.as_ref().to_string(),
+            ServerName::IpAddress(addr) => addr.to_string(),
+            other => format!("{:?}", other),
+        };
+        self.capture.store.lock().unwrap().insert(key, end_entity.0.clone());
+        Ok(ServerCertVerified::assertion())
+    }
+}
+
+/// Builds a `rustls::ClientConfig` that accepts any certificate (matching the
+/// rest of httprs' relaxed TLS posture) while recording each leaf certificate
+/// it sees into `capture`.
+pub fn recording_client_config(capture: CertCapture) -> ClientConfig {
+    ClientConfig::builder()
+        .with_safe_defaults()
+        .with_custom_certificate_verifier(Arc::new(RecordingVerifier { capture }))
+        .with_no_client_auth()
+}
@@ -0,0 +1,205 @@
+use colored::{Color, Colorize};
+use regex::bytes::Regex;
+use reqwest::header::{CONTENT_LENGTH, CONTENT_RANGE, RANGE};
+use reqwest::{Client, StatusCode};
+use tokio::io::{BufReader, Lines, Stdin};
+use tokio::task::JoinHandle;
+
+use crate::{get_url_variants, Config};
+
+/// Per-host tailing state: how many bytes of the body we've already seen.
+struct Cursor {
+    offset: u64,
+    /// A line fragment left over from the previous poll that hadn't been
+    /// terminated by '\n' yet.
+    pending_line: Vec<u8>,
+}
+
+/// Extracts the total resource length from a `Content-Range: bytes a-b/total`
+/// header, or falls back to `Content-Length` for servers that ignore Range.
+fn total_length(res: &reqwest::Response) -> Option<u64> {
+    if let Some(value) = res.headers().get(CONTENT_RANGE) {
+        let value = value.to_str().ok()?;
+        let total = value.rsplit('/').next()?;
+        return total.parse().ok();
+    }
+    res.headers()
+        .get(CONTENT_LENGTH)?
+        .to_str()
+        .ok()?
+        .parse()
+        .ok()
+}
+
+/// Learns the offset to start tailing `url` from, via a zero-byte Range
+/// probe. Returns `None` only when the host couldn't be reached at all; a
+/// server that doesn't expose a Content-Length/Content-Range total (chunked
+/// or streamed responses, which most HTTP-exposed logs are) still gets
+/// watched, just starting from offset 0 instead of the current end.
+async fn probe_length(client: &Client, url: &str) -> Option<u64> {
+    let res = client
+        .get(url)
+        .header(RANGE, "bytes=0-0")
+        .send()
+        .await
+        .ok()?;
+
+    match total_length(&res) {
+        Some(total) => Some(total),
+        None => {
+            eprintln!(
+                "{}: no Content-Length/Content-Range total, tailing from offset 0",
+                url
+            );
+            Some(0)
+        }
+    }
+}
+
+/// Requests the bytes appended since `cursor.offset`, matches complete lines
+/// against `regexes`, and prints any that match. Returns `true` if the poll
+/// succeeded (regardless of whether new data was found).
+async fn poll_once(
+    client: &Client,
+    url: &str,
+    regexes: &Vec<Regex>,
+    cursor: &mut Cursor,
+) -> bool {
+    let res = match client
+        .get(url)
+        .header(RANGE, format!("bytes={}-", cursor.offset))
+        .send()
+        .await
+    {
+        Ok(res) => res,
+        Err(_) => return false,
+    };
+
+    let status = res.status();
+    if status == StatusCode::RANGE_NOT_SATISFIABLE {
+        // Nothing new since last poll, unless the resource shrank below our
+        // offset (rotated/truncated): then start over from the beginning.
+        if matches!(total_length(&res), Some(total) if total < cursor.offset) {
+            cursor.offset = 0;
+            cursor.pending_line.clear();
+        }
+        return true;
+    }
+
+    let total = total_length(&res);
+    let body = match res.bytes().await {
+        Ok(body) => body,
+        Err(_) => return false,
+    };
+
+    let new_bytes: &[u8] = match status {
+        StatusCode::PARTIAL_CONTENT => body.as_ref(),
+        // Server ignored the Range header and sent the whole body back; if
+        // it shrank below our offset the resource was rotated/truncated.
+        _ => {
+            let total = total.unwrap_or(body.len() as u64);
+            if total < cursor.offset {
+                cursor.offset = 0;
+                cursor.pending_line.clear();
+                body.as_ref()
+            } else {
+                // A server can claim a length that doesn't match what it
+                // actually sends; don't panic on a short body.
+                body.as_ref()
+                    .get(cursor.offset as usize..)
+                    .unwrap_or(&[])
+            }
+        }
+    };
+
+    cursor.offset += new_bytes.len() as u64;
+
+    let mut chunk = std::mem::take(&mut cursor.pending_line);
+    chunk.extend_from_slice(new_bytes);
+
+    let mut lines: Vec<&[u8]> = chunk.split(|&b| b == b'\n').collect();
+    // The last element is either empty (chunk ended in '\n') or a partial
+    // line to carry over to the next poll.
+    cursor.pending_line = lines.pop().unwrap_or(&[]).to_vec();
+
+    for line in lines {
+        let matches = regexes
+            .iter()
+            .filter_map(|re| re.captures(line))
+            .filter_map(|caps| caps.get(1).or(caps.get(0)))
+            .map(|m| m.as_bytes())
+            .map(|value| String::from_utf8_lossy(value).to_string())
+            .collect::<Vec<String>>();
+
+        let line = String::from_utf8_lossy(line);
+        if !matches.is_empty() {
+            println!("{} {}", line, matches.join(" ").color(Color::Cyan));
+        } else if regexes.is_empty() {
+            println!("{}", line);
+        }
+    }
+
+    true
+}
+
+/// Tails each host over HTTP, polling for appended bytes via incremental
+/// `Range` requests instead of exiting after a single pass like [`crate::process`].
+pub async fn watch(
+    mut host_lines: Lines<BufReader<Stdin>>,
+    config: &Config,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let client = Client::builder()
+        .danger_accept_invalid_certs(true)
+        .timeout(std::time::Duration::from_millis(config.timeout))
+        .redirect(reqwest::redirect::Policy::none())
+        .tcp_keepalive(None)
+        .tcp_nodelay(true)
+        .https_only(false)
+        .pool_max_idle_per_host(0)
+        .user_agent("httprs/0.1.0")
+        .build()
+        .unwrap();
+
+    let regexes = if let Some(path) = &config.match_regexes_path {
+        crate::parse_regexes(path).await
+    } else {
+        vec![]
+    };
+
+    let interval = std::time::Duration::from_millis(config.interval);
+    // Unlike `process`'s transient per-host requests, a watcher task runs
+    // for the lifetime of the program and never returns its permit — bounding
+    // spawns with a one-shot `Semaphore` would cap watched hosts at
+    // `config.tasks` and deadlock `next_line()` forever after that. Watchers
+    // are cheap while idle (sleeping between polls), so leave them unbounded.
+    let mut handles: Vec<JoinHandle<()>> = vec![];
+
+    while let Some(host) = host_lines.next_line().await.unwrap() {
+        let regexes = regexes.clone();
+        let client = client.clone();
+
+        handles.push(tokio::spawn(async move {
+            for url in get_url_variants(host) {
+                let Some(offset) = probe_length(&client, &url).await else {
+                    continue;
+                };
+
+                let mut cursor = Cursor {
+                    offset,
+                    pending_line: vec![],
+                };
+
+                loop {
+                    poll_once(&client, &url, &regexes, &mut cursor).await;
+                    tokio::time::sleep(interval).await;
+                }
+            }
+        }));
+    }
+
+    for handle in handles {
+        handle.await.expect("fatal error in a task")
+    }
+
+    Ok(())
+}